@@ -0,0 +1,336 @@
+use std::collections::{HashMap, HashSet};
+
+use ethers::core::types::{Address, U256};
+
+use crate::{
+    protocols::WETH_ADDRESS,
+    types::{Pool, PoolExtra},
+};
+
+/// Negative-weight cycles whose summed weight is within this of zero are
+/// floating-point noise, not a real arbitrage.
+const PROFIT_EPSILON: f64 = 1e-9;
+
+const MAX_CYCLE_HOPS: usize = 4;
+
+#[derive(Debug, Clone, Copy)]
+pub enum PoolQuote {
+    V2 { reserve0: U256, reserve1: U256 },
+    V3 { sqrt_price_x96: U256, liquidity: u128 },
+}
+
+#[derive(Debug, Clone)]
+pub struct Hop {
+    pub pool: Address,
+    pub token_in: Address,
+    pub token_out: Address,
+}
+
+/// A profitable round trip back to its starting token.
+#[derive(Debug, Clone)]
+pub struct ArbOpportunity {
+    pub hops: Vec<Hop>,
+    /// Sum of `-ln(effective_rate)` over the cycle; negative means the round
+    /// trip multiplies value by `exp(-profit_log_sum) > 1`.
+    pub profit_log_sum: f64,
+}
+
+struct Edge {
+    pool: Address,
+    token_in: Address,
+    token_out: Address,
+    weight: f64,
+}
+
+pub struct ArbGraph {
+    edges: Vec<Edge>,
+    nodes: Vec<Address>,
+}
+
+impl ArbGraph {
+    pub fn build(pools: &HashMap<Address, Pool>, quotes: &HashMap<Address, PoolQuote>) -> Self {
+        let mut edges = Vec::new();
+        let mut nodes = HashSet::new();
+
+        for pool in pools.values() {
+            let Some(quote) = quotes.get(&pool.pool) else { continue };
+
+            for (token_a, token_b) in pool.token01_pair() {
+                let Some(rate_ab) = effective_rate(pool, quote, token_a, token_b) else { continue };
+                let Some(rate_ba) = effective_rate(pool, quote, token_b, token_a) else { continue };
+
+                nodes.insert(token_a);
+                nodes.insert(token_b);
+
+                edges.push(Edge { pool: pool.pool, token_in: token_a, token_out: token_b, weight: -rate_ab.ln() });
+                edges.push(Edge { pool: pool.pool, token_in: token_b, token_out: token_a, weight: -rate_ba.ln() });
+            }
+        }
+
+        Self { edges, nodes: nodes.into_iter().collect() }
+    }
+
+    /// Runs Bellman-Ford seeded from WETH and returns every distinct
+    /// profitable cycle found, capped at `MAX_CYCLE_HOPS` and deduplicated
+    /// across rotations of the same cycle.
+    pub fn find_opportunities(&self, pools: &HashMap<Address, Pool>) -> Vec<ArbOpportunity> {
+        if self.nodes.is_empty() {
+            return Vec::new();
+        }
+
+        let source = *WETH_ADDRESS;
+        let mut dist: HashMap<Address, f64> = self.nodes.iter().map(|n| (*n, f64::INFINITY)).collect();
+        let mut pred: HashMap<Address, usize> = HashMap::new();
+        dist.insert(source, 0.0);
+
+        for _ in 0..self.nodes.len().saturating_sub(1) {
+            let mut relaxed_any = false;
+            for (i, edge) in self.edges.iter().enumerate() {
+                relax(&mut dist, &mut pred, edge, i, &mut relaxed_any);
+            }
+            if !relaxed_any {
+                break;
+            }
+        }
+
+        // One extra pass: any edge that still relaxes touches (or is
+        // reachable from) a negative-weight cycle.
+        let mut on_cycle = HashSet::new();
+        for (i, edge) in self.edges.iter().enumerate() {
+            let mut relaxed_any = false;
+            if relax(&mut dist, &mut pred, edge, i, &mut relaxed_any) {
+                on_cycle.insert(edge.token_out);
+            }
+        }
+
+        let mut seen_rotations: HashSet<Vec<Address>> = HashSet::new();
+        let mut opportunities = Vec::new();
+
+        for start in on_cycle {
+            // Walk back |V| predecessor steps to guarantee landing inside
+            // the cycle rather than on its approach path.
+            let mut node = start;
+            for _ in 0..self.nodes.len() {
+                node = match pred.get(&node) {
+                    Some(&edge_idx) => self.edges[edge_idx].token_in,
+                    None => break,
+                };
+            }
+
+            let Some(cycle_edges) = collect_cycle(node, &pred, &self.edges, MAX_CYCLE_HOPS) else { continue };
+
+            let mut rotation_key: Vec<Address> = cycle_edges.iter().map(|e| e.token_in).collect();
+            let min_pos = rotation_key.iter().enumerate().min_by_key(|(_, a)| **a).map(|(i, _)| i).unwrap_or(0);
+            rotation_key.rotate_left(min_pos);
+            if !seen_rotations.insert(rotation_key) {
+                continue;
+            }
+
+            let profit_log_sum: f64 = cycle_edges.iter().map(|e| e.weight).sum();
+            if profit_log_sum >= -PROFIT_EPSILON {
+                continue;
+            }
+
+            let hops = cycle_edges
+                .iter()
+                .filter(|e| pools.contains_key(&e.pool))
+                .map(|e| Hop { pool: e.pool, token_in: e.token_in, token_out: e.token_out })
+                .collect::<Vec<_>>();
+
+            if hops.len() != cycle_edges.len() {
+                continue;
+            }
+
+            opportunities.push(ArbOpportunity { hops, profit_log_sum });
+        }
+
+        opportunities
+    }
+}
+
+fn relax(
+    dist: &mut HashMap<Address, f64>,
+    pred: &mut HashMap<Address, usize>,
+    edge: &Edge,
+    edge_index: usize,
+    relaxed_any: &mut bool,
+) -> bool {
+    let Some(&d_in) = dist.get(&edge.token_in) else { return false };
+    if d_in == f64::INFINITY {
+        return false;
+    }
+
+    let candidate = d_in + edge.weight;
+    let d_out = dist.get(&edge.token_out).copied().unwrap_or(f64::INFINITY);
+
+    if candidate < d_out - PROFIT_EPSILON {
+        dist.insert(edge.token_out, candidate);
+        pred.insert(edge.token_out, edge_index);
+        *relaxed_any = true;
+        true
+    } else {
+        false
+    }
+}
+
+/// Follows predecessor pointers from `start` until a node repeats, which
+/// closes the cycle. Bails out past `max_hops` since anything longer isn't
+/// worth reporting.
+fn collect_cycle<'a>(start: Address, pred: &HashMap<Address, usize>, edges: &'a [Edge], max_hops: usize) -> Option<Vec<&'a Edge>> {
+    let mut path = Vec::new();
+    let mut node = start;
+    let mut visited = HashSet::new();
+    visited.insert(node);
+
+    loop {
+        let edge_idx = *pred.get(&node)?;
+        let edge = &edges[edge_idx];
+        path.push(edge);
+        node = edge.token_in;
+
+        if node == start {
+            break;
+        }
+        if !visited.insert(node) {
+            // Looped without returning to `start`; the cycle we actually
+            // want is the suffix from this repeat back to itself.
+            let repeat_pos = path.iter().position(|e| e.token_in == node)?;
+            path.drain(0..=repeat_pos);
+            break;
+        }
+        if path.len() > max_hops {
+            return None;
+        }
+    }
+
+    path.reverse();
+    if path.len() > max_hops || path.is_empty() {
+        return None;
+    }
+
+    Some(path)
+}
+
+/// The marginal price of `token_out` per `token_in`, net of the pool fee.
+fn effective_rate(pool: &Pool, quote: &PoolQuote, token_in: Address, token_out: Address) -> Option<f64> {
+    match (&pool.extra, quote) {
+        (PoolExtra::UniSwapV2 { fee }, PoolQuote::V2 { reserve0, reserve1 }) => {
+            let (reserve_in, reserve_out) = if token_in == pool.token0_type() {
+                (*reserve0, *reserve1)
+            } else if token_in == pool.token1_type() {
+                (*reserve1, *reserve0)
+            } else {
+                return None;
+            };
+
+            if reserve_in.is_zero() || reserve_out.is_zero() {
+                return None;
+            }
+
+            let price = reserve_out.to_f64_lossy() / reserve_in.to_f64_lossy();
+            Some(price * (1.0 - (*fee as f64 / 1_000_000.0)))
+        }
+        (PoolExtra::UniSwapV3 { fee }, PoolQuote::V3 { sqrt_price_x96, liquidity }) => {
+            // A pool with no liquidity in range can't actually fill a swap at
+            // this price; treat it the same as a drained V2 reserve above.
+            if *liquidity == 0 {
+                return None;
+            }
+
+            // price of token0 in terms of token1: (sqrtPriceX96 / 2^96)^2
+            let sqrt_price = sqrt_price_x96.to_f64_lossy() / 2f64.powi(96);
+            let price_token0_in_token1 = sqrt_price * sqrt_price;
+
+            let raw_rate = if token_in == pool.token0_type() && token_out == pool.token1_type() {
+                price_token0_in_token1
+            } else if token_in == pool.token1_type() && token_out == pool.token0_type() {
+                1.0 / price_token0_in_token1
+            } else {
+                return None;
+            };
+
+            if !raw_rate.is_finite() || raw_rate <= 0.0 {
+                return None;
+            }
+
+            Some(raw_rate * (1.0 - (*fee as f64 / 1_000_000.0)))
+        }
+        _ => None,
+    }
+}
+
+trait ToF64Lossy {
+    fn to_f64_lossy(&self) -> f64;
+}
+
+impl ToF64Lossy for U256 {
+    fn to_f64_lossy(&self) -> f64 {
+        self.to_string().parse().unwrap_or(f64::INFINITY)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::Token;
+
+    fn v2_pool(pool: Address, token0: Address, token1: Address, reserve0: u64, reserve1: u64) -> (Pool, PoolQuote) {
+        let pool = Pool {
+            protocol: crate::types::Protocol::UniSwapV2,
+            pool,
+            tokens: vec![Token::new(&token0, 18), Token::new(&token1, 18)],
+            extra: PoolExtra::UniSwapV2 { fee: 3_000 },
+        };
+        let quote = PoolQuote::V2 { reserve0: reserve0.into(), reserve1: reserve1.into() };
+        (pool, quote)
+    }
+
+    fn build_triangle(rate_weth_a: (u64, u64), rate_a_b: (u64, u64), rate_b_weth: (u64, u64)) -> (HashMap<Address, Pool>, HashMap<Address, PoolQuote>) {
+        let weth = *WETH_ADDRESS;
+        let token_a = Address::from_low_u64_be(1);
+        let token_b = Address::from_low_u64_be(2);
+
+        let (pool_weth_a, quote_weth_a) = v2_pool(Address::from_low_u64_be(101), weth, token_a, rate_weth_a.0, rate_weth_a.1);
+        let (pool_a_b, quote_a_b) = v2_pool(Address::from_low_u64_be(102), token_a, token_b, rate_a_b.0, rate_a_b.1);
+        let (pool_b_weth, quote_b_weth) = v2_pool(Address::from_low_u64_be(103), token_b, weth, rate_b_weth.0, rate_b_weth.1);
+
+        let mut quotes = HashMap::new();
+        quotes.insert(pool_weth_a.pool, quote_weth_a);
+        quotes.insert(pool_a_b.pool, quote_a_b);
+        quotes.insert(pool_b_weth.pool, quote_b_weth);
+
+        let mut pools = HashMap::new();
+        pools.insert(pool_weth_a.pool, pool_weth_a);
+        pools.insert(pool_a_b.pool, pool_a_b);
+        pools.insert(pool_b_weth.pool, pool_b_weth);
+
+        (pools, quotes)
+    }
+
+    #[test]
+    fn finds_profitable_triangle_cycle_exactly_once() {
+        // WETH->A at 2x, A->B at 2x, B->WETH at 0.3x: a 1.2x round trip
+        // before fees, comfortably profitable after three 0.3% fees.
+        let (pools, quotes) = build_triangle((100, 200), (100, 200), (1_000, 300));
+
+        let graph = ArbGraph::build(&pools, &quotes);
+        let opportunities = graph.find_opportunities(&pools);
+
+        assert_eq!(opportunities.len(), 1, "rotations of the same cycle must be deduplicated");
+        let opp = &opportunities[0];
+        assert_eq!(opp.hops.len(), 3);
+        assert!(opp.profit_log_sum < -PROFIT_EPSILON);
+    }
+
+    #[test]
+    fn does_not_report_a_breakeven_cycle_as_profit() {
+        // Rates multiply to ~1.0 before fees; fees alone push it underwater,
+        // but not enough for floating-point noise to look like a real cycle.
+        let (pools, quotes) = build_triangle((100, 100), (100, 100), (100, 100));
+
+        let graph = ArbGraph::build(&pools, &quotes);
+        let opportunities = graph.find_opportunities(&pools);
+
+        assert!(opportunities.is_empty());
+    }
+}
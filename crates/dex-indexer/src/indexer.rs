@@ -0,0 +1,349 @@
+use std::{collections::{HashMap, VecDeque}, sync::Arc};
+
+use ethers::{
+    core::types::{Address, BlockNumber, H256},
+    providers::{Middleware, Provider},
+};
+use eyre::{ensure, eyre, Result};
+
+use crate::{
+    protocols::{default_registry, ProtocolRegistry},
+    types::Pool,
+};
+
+/// Resolves the parent hash of a given block number on the remote canonical
+/// chain. Lets `handle_reorg`'s walk-back logic be exercised against a fake
+/// chain in tests instead of a live provider.
+#[async_trait::async_trait]
+trait ParentHashResolver {
+    async fn parent_hash_of(&self, block_number: u64) -> Result<H256>;
+}
+
+#[async_trait::async_trait]
+impl ParentHashResolver for Provider<ethers::providers::Http> {
+    async fn parent_hash_of(&self, block_number: u64) -> Result<H256> {
+        let block = self
+            .get_block(BlockNumber::Number(block_number.into()))
+            .await?
+            .ok_or_else(|| eyre!("block {} disappeared during reorg walk", block_number))?;
+        Ok(block.parent_hash)
+    }
+}
+
+/// Reorgs deeper than this are treated as unrecoverable.
+const REORG_WINDOW: usize = 64;
+
+#[derive(Debug, Clone, Copy)]
+struct BlockRecord {
+    number: u64,
+    hash: H256,
+    parent_hash: H256,
+}
+
+#[derive(Debug, Clone)]
+pub enum PoolDelta {
+    Added(Pool),
+    Reverted(Address),
+}
+
+pub struct PoolIndexer {
+    provider: Arc<Provider<ethers::providers::Http>>,
+    registry: ProtocolRegistry,
+    pools: HashMap<Address, Pool>,
+    /// Addresses of pools first seen in a given block, so a revert of that
+    /// block can undo exactly the insertions it caused. Evicted in lockstep
+    /// with `blocks` so this never outlives the reorg window it backs.
+    pools_by_block: HashMap<u64, Vec<Address>>,
+    blocks: VecDeque<BlockRecord>,
+}
+
+impl PoolIndexer {
+    pub fn new(provider: Arc<Provider<ethers::providers::Http>>) -> Self {
+        Self {
+            provider,
+            registry: default_registry(),
+            pools: HashMap::new(),
+            pools_by_block: HashMap::new(),
+            blocks: VecDeque::with_capacity(REORG_WINDOW),
+        }
+    }
+
+    pub fn pools(&self) -> &HashMap<Address, Pool> {
+        &self.pools
+    }
+
+    pub fn head(&self) -> Option<u64> {
+        self.blocks.back().map(|b| b.number)
+    }
+
+    pub fn seed(&mut self, pools: HashMap<Address, Pool>, cursor: (u64, H256)) {
+        self.pools = pools;
+        self.blocks.clear();
+        self.pools_by_block.clear();
+        self.blocks.push_back(BlockRecord {
+            number: cursor.0,
+            hash: cursor.1,
+            // unknown at seed time; the next fetched block only needs to
+            // chain onto `hash`, not re-verify this one.
+            parent_hash: H256::zero(),
+        });
+    }
+
+    pub async fn process_next_block(&mut self) -> Result<Vec<PoolDelta>> {
+        let next_number = self.head().map(|n| n + 1).unwrap_or(0);
+        let block = self
+            .provider
+            .get_block(BlockNumber::Number(next_number.into()))
+            .await?
+            .ok_or_else(|| eyre!("block {} not yet available", next_number))?;
+
+        let hash = block.hash.ok_or_else(|| eyre!("pending block has no hash"))?;
+        let parent_hash = block.parent_hash;
+
+        let mut deltas = Vec::new();
+        let mut resume_from = next_number;
+
+        if let Some(tip) = self.blocks.back() {
+            if tip.hash != parent_hash {
+                let (ancestor_number, reorg_deltas) = self.handle_reorg(next_number, parent_hash).await?;
+                deltas.extend(reorg_deltas);
+                resume_from = ancestor_number + 1;
+            }
+        }
+
+        for number in resume_from..=next_number {
+            let record = if number == next_number {
+                BlockRecord { number, hash, parent_hash }
+            } else {
+                let block = self
+                    .provider
+                    .get_block(BlockNumber::Number(number.into()))
+                    .await?
+                    .ok_or_else(|| eyre!("block {} not found while resuming after reorg", number))?;
+                BlockRecord {
+                    number,
+                    hash: block.hash.ok_or_else(|| eyre!("block {} has no hash", number))?,
+                    parent_hash: block.parent_hash,
+                }
+            };
+
+            let added = self.index_block(record).await?;
+            deltas.extend(added.into_iter().map(PoolDelta::Added));
+
+            self.blocks.push_back(record);
+            if self.blocks.len() > REORG_WINDOW {
+                if let Some(evicted) = self.blocks.pop_front() {
+                    self.pools_by_block.remove(&evicted.number);
+                }
+            }
+        }
+
+        Ok(deltas)
+    }
+
+    /// Walks backwards from `orphaned_parent_hash` to the nearest block we
+    /// still agree with, reverting every pool inserted by the blocks past
+    /// that ancestor. Returns the ancestor's number so the caller resumes
+    /// forward indexing from `ancestor_number + 1` instead of jumping
+    /// straight to the new tip and missing the re-indexed range.
+    async fn handle_reorg(&mut self, new_block: u64, orphaned_parent_hash: H256) -> Result<(u64, Vec<PoolDelta>)> {
+        reorg(
+            &mut self.blocks,
+            &mut self.pools_by_block,
+            &mut self.pools,
+            new_block,
+            orphaned_parent_hash,
+            self.provider.as_ref(),
+        )
+        .await
+    }
+
+    async fn index_block(&mut self, record: BlockRecord) -> Result<Vec<Pool>> {
+        let mut added = Vec::new();
+
+        for protocol in self.registry.protocols() {
+            let filter = protocol
+                .event_filter(record.number)
+                .to_block(BlockNumber::Number(record.number.into()));
+
+            let logs = self.provider.get_logs(&filter).await?;
+            for log in logs {
+                let pool = protocol.decode_pool_created(&log, self.provider.clone()).await?;
+                if self.pools.insert(pool.pool, pool.clone()).is_none() {
+                    self.pools_by_block.entry(record.number).or_default().push(pool.pool);
+                }
+                added.push(pool);
+            }
+        }
+
+        Ok(added)
+    }
+}
+
+fn bail_reorg_too_deep(new_block: u64, oldest_known: Option<u64>) -> eyre::Error {
+    eyre!(
+        "reorg around block {} reaches past the {}-block window (oldest known: {:?}); cannot recover without a fresh backfill",
+        new_block,
+        REORG_WINDOW,
+        oldest_known
+    )
+}
+
+/// Walks backwards from `orphaned_parent_hash` to the nearest block still
+/// present in `blocks`, reverting every pool inserted by the blocks past
+/// that ancestor. Returns the ancestor's number so the caller resumes
+/// forward indexing from `ancestor_number + 1` instead of jumping straight
+/// to the new tip and missing the re-indexed range.
+async fn reorg(
+    blocks: &mut VecDeque<BlockRecord>,
+    pools_by_block: &mut HashMap<u64, Vec<Address>>,
+    pools: &mut HashMap<Address, Pool>,
+    new_block: u64,
+    orphaned_parent_hash: H256,
+    resolver: &impl ParentHashResolver,
+) -> Result<(u64, Vec<PoolDelta>)> {
+    ensure!(!blocks.is_empty(), "cannot reorg an empty indexer");
+
+    let mut expected_hash = orphaned_parent_hash;
+    let mut ancestor_index = None;
+
+    for (i, record) in blocks.iter().enumerate().rev() {
+        if record.hash == expected_hash {
+            ancestor_index = Some(i);
+            break;
+        }
+        // Walk one block further back on the new canonical chain.
+        expected_hash = resolver.parent_hash_of(record.number).await?;
+    }
+
+    let ancestor_index =
+        ancestor_index.ok_or_else(|| bail_reorg_too_deep(new_block, blocks.front().map(|b| b.number)))?;
+    let ancestor_number = blocks[ancestor_index].number;
+
+    let orphaned: Vec<BlockRecord> = blocks.drain(ancestor_index + 1..).collect();
+
+    let mut deltas = Vec::new();
+    for record in orphaned.into_iter().rev() {
+        if let Some(addrs) = pools_by_block.remove(&record.number) {
+            for addr in addrs {
+                pools.remove(&addr);
+                deltas.push(PoolDelta::Reverted(addr));
+            }
+        }
+    }
+
+    Ok((ancestor_number, deltas))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A fake canonical chain keyed by block number, standing in for a live
+    /// provider in the reorg walk-back test below.
+    struct FakeChain(HashMap<u64, H256>);
+
+    #[async_trait::async_trait]
+    impl ParentHashResolver for FakeChain {
+        async fn parent_hash_of(&self, block_number: u64) -> Result<H256> {
+            self.0
+                .get(&block_number)
+                .copied()
+                .ok_or_else(|| eyre!("no fake block {}", block_number))
+        }
+    }
+
+    fn hash(tag: u8) -> H256 {
+        H256::from_low_u64_be(tag as u64)
+    }
+
+    fn record(number: u64, hash: H256, parent_hash: H256) -> BlockRecord {
+        BlockRecord { number, hash, parent_hash }
+    }
+
+    fn pool_at(seed: u8) -> (Address, Pool) {
+        let addr = Address::from_low_u64_be(seed as u64);
+        (
+            addr,
+            Pool {
+                protocol: crate::types::Protocol::UniSwapV2,
+                pool: addr,
+                tokens: Vec::new(),
+                extra: crate::types::PoolExtra::None,
+            },
+        )
+    }
+
+    #[tokio::test]
+    async fn reorg_reverts_orphaned_blocks_and_resumes_from_ancestor() {
+        // Local canonical view before the reorg: 98 -(h98)-> 99 -(h99)-> 100 -(h100)->
+        let h97 = hash(97);
+        let h98 = hash(98);
+        let h99 = hash(99);
+        let h100_old = hash(100);
+
+        let mut blocks = VecDeque::from(vec![
+            record(98, h98, h97),
+            record(99, h99, h98),
+            record(100, h100_old, h99),
+        ]);
+
+        let (pool_addr, pool) = pool_at(1);
+        let mut pools = HashMap::new();
+        pools.insert(pool_addr, pool);
+        let mut pools_by_block = HashMap::new();
+        pools_by_block.insert(100, vec![pool_addr]);
+
+        // The chain reorganized block 100: the new block 101's parent is a
+        // different block 100 that still chains onto the unchanged 99.
+        let h100_new = hash(200);
+        let chain = FakeChain(HashMap::from([(100, h99)]));
+
+        let (ancestor_number, deltas) = reorg(
+            &mut blocks,
+            &mut pools_by_block,
+            &mut pools,
+            101,
+            h100_new,
+            &chain,
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(ancestor_number, 99);
+        assert_eq!(blocks.len(), 2);
+        assert_eq!(blocks.back().unwrap().number, 99);
+        assert!(matches!(deltas.as_slice(), [PoolDelta::Reverted(addr)] if *addr == pool_addr));
+        assert!(!pools.contains_key(&pool_addr));
+        assert!(!pools_by_block.contains_key(&100));
+    }
+
+    #[tokio::test]
+    async fn reorg_too_deep_errors_instead_of_silently_mis_indexing() {
+        let mut blocks = VecDeque::from(vec![record(100, hash(100), hash(99))]);
+        let mut pools = HashMap::new();
+        let mut pools_by_block = HashMap::new();
+        // The new block 100 descends from a block 99 that the window no
+        // longer holds, so the walk runs out of local blocks.
+        let chain = FakeChain(HashMap::from([(100, hash(249))]));
+
+        let result = reorg(&mut blocks, &mut pools_by_block, &mut pools, 101, hash(250), &chain).await;
+
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn seed_clears_pools_by_block_along_with_blocks() {
+        let provider = Arc::new(Provider::<ethers::providers::Http>::try_from("http://localhost:8545").unwrap());
+        let mut indexer = PoolIndexer::new(provider);
+
+        let (pool_addr, pool) = pool_at(1);
+        indexer.pools.insert(pool_addr, pool);
+        indexer.pools_by_block.insert(100, vec![pool_addr]);
+        indexer.blocks.push_back(record(100, hash(100), hash(99)));
+
+        indexer.seed(HashMap::new(), (200, hash(200)));
+
+        assert!(indexer.pools_by_block.is_empty(), "stale pools_by_block entries must not survive a reseed");
+    }
+}
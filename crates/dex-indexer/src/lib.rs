@@ -0,0 +1,6 @@
+pub mod arbitrage;
+pub mod indexer;
+pub mod protocols;
+pub mod store;
+pub mod stream;
+pub mod types;
@@ -1,9 +1,20 @@
 pub mod uniswapv2;
 pub mod uniswapv3;
 
+use std::{collections::HashMap, sync::Arc};
+
+use burberry::async_trait;
 use cached::proc_macro::cached;
-use ethers::{abi::Address, providers::{Http, Provider}};
-use eyre::{bail, ensure, eyre, Ok, Result};
+use ethers::{
+    abi::Address,
+    contract::abigen,
+    core::types::{Filter, Log, H256},
+    providers::{Http, Middleware, Provider},
+};
+use eyre::{eyre, Result};
+use once_cell::sync::Lazy;
+
+use crate::types::{Pool, SwapEvent};
 
 abigen!(
         Erc20,
@@ -37,3 +48,63 @@ pub async fn get_coin_decimals<M: Middleware>(
         }
     }
 }
+
+/// Implementing this and registering it in `default_registry` is how a new
+/// venue (Curve, Balancer, Uniswap V4, ...) takes part in indexing and swap
+/// decoding: `PoolIndexer`, `backfill` and `SwapStream` each build that
+/// registry and dispatch through it by topic.
+#[async_trait]
+pub trait DexProtocol: Send + Sync {
+    fn swap_topic(&self) -> H256;
+
+    fn factory_event_topic(&self) -> H256;
+
+    fn factory_address(&self) -> Address;
+
+    fn event_filter(&self, block: u64) -> Filter;
+
+    async fn decode_pool_created(&self, log: &Log, provider: Arc<Provider<Http>>) -> Result<Pool>;
+
+    async fn decode_swap(&self, log: &Log, provider: Arc<Provider<Http>>) -> Result<SwapEvent>;
+}
+
+/// Table-driven topic -> protocol resolution.
+#[derive(Default)]
+pub struct ProtocolRegistry {
+    by_swap_topic: HashMap<H256, Arc<dyn DexProtocol>>,
+    by_factory_topic: HashMap<H256, Arc<dyn DexProtocol>>,
+}
+
+impl ProtocolRegistry {
+    pub fn new() -> Self {
+        Self { by_swap_topic: HashMap::new(), by_factory_topic: HashMap::new() }
+    }
+
+    pub fn register(&mut self, protocol: Arc<dyn DexProtocol>) {
+        self.by_swap_topic.insert(protocol.swap_topic(), protocol.clone());
+        self.by_factory_topic.insert(protocol.factory_event_topic(), protocol);
+    }
+
+    pub fn by_swap_topic(&self, topic: &H256) -> Option<&Arc<dyn DexProtocol>> {
+        self.by_swap_topic.get(topic)
+    }
+
+    pub fn by_factory_topic(&self, topic: &H256) -> Option<&Arc<dyn DexProtocol>> {
+        self.by_factory_topic.get(topic)
+    }
+
+    pub fn protocols(&self) -> impl Iterator<Item = &Arc<dyn DexProtocol>> {
+        self.by_swap_topic.values()
+    }
+
+    pub fn swap_topics(&self) -> impl Iterator<Item = &H256> {
+        self.by_swap_topic.keys()
+    }
+}
+
+pub fn default_registry() -> ProtocolRegistry {
+    let mut registry = ProtocolRegistry::new();
+    registry.register(Arc::new(uniswapv2::UniswapV2Protocol));
+    registry.register(Arc::new(uniswapv3::UniswapV3Protocol));
+    registry
+}
@@ -6,7 +6,20 @@ use ethers::{
 use eyre::{ensure, Ok, Result};
 use serde::Deserialize;
 
-use crate::{protocols::get_coin_decimals, types::{Pool, PoolExtra, Protocol, Token}};
+use crate::{
+    protocols::{get_coin_decimals, DexProtocol},
+    types::{Pool, PoolExtra, Protocol, SwapEvent, Token},
+};
+
+pub const UNISWAP_V2_SWAP_TOPIC: H256 = H256([
+    0xd7, 0x8a, 0xd9, 0x5f, 0xa4, 0x6c, 0x99, 0x4b, 0x65, 0x51, 0xd0, 0xda, 0x85, 0xfc, 0x27, 0x5f,
+    0xe6, 0x13, 0xce, 0x37, 0x65, 0x7f, 0xb8, 0xd5, 0xe3, 0xd1, 0x30, 0x84, 0x01, 0x59, 0xd8, 0x22,
+]);
+
+pub const UNISWAP_V2_PAIR_CREATED_TOPIC: H256 = H256([
+    0x0d, 0x36, 0x48, 0xbd, 0x0f, 0x6b, 0xa8, 0x01, 0x34, 0xa3, 0x3b, 0xa9, 0x27, 0x5a, 0xc5, 0x85,
+    0xd9, 0xd3, 0x15, 0xf0, 0xad, 0x83, 0x55, 0xcd, 0xde, 0xfd, 0xe3, 0x1a, 0xfa, 0x28, 0xd0, 0xe9,
+]);
 
 abigen!(
     IUniswapV2Pool,
@@ -28,7 +41,7 @@ const V2FACTORY_ADDRESS: &str = "0x5C69bEe701ef814a2B6a3EDD4B1652CB9cc5aA6f";
 
 pub fn uniswapv2_event_filter(block: u64) -> Filter {
     Filter::new()
-        .address(V2FACTORY_ADDRESS.parse::<Address>()?)
+        .address(V2FACTORY_ADDRESS.parse::<Address>().expect("valid V2 factory address"))
         .event("PairCreated(address indexed token0, address indexed token1, address pair, uint)")
         .from_block(block)
 }
@@ -43,10 +56,10 @@ pub struct UniswapV2PairCreated {
 impl TryFrom<&Log> for UniswapV2PairCreated {
     type Error = eyre::Error;
 
-    fn try_from(value: &Log) -> std::result::Result<Self> {
-        let token0 = Address::from(log.topics[1]);
-        let token1 = Address::from(log.topics[2]);
-        let pair = Address::from(&log.data[12..32].try_into()?);
+    fn try_from(value: &Log) -> std::result::Result<Self, Self::Error> {
+        let token0 = Address::from(value.topics[1]);
+        let token1 = Address::from(value.topics[2]);
+        let pair = Address::from(value.data[12..32].try_into()?);
 
         Ok(Self { 
             pair: pair,
@@ -66,7 +79,9 @@ impl UniswapV2PairCreated {
             Token::new(&self.token1, token1_decimals),
         ];
 
-        let extra = PoolExtra::UniSwapV2 { fee: 5 };
+        // 3000 parts-per-million == 0.3%, the standard V2 swap fee; must stay
+        // in the same ppm convention arbitrage.rs's effective_rate expects.
+        let extra = PoolExtra::UniSwapV2 { fee: 3_000 };
 
         Ok(Pool { 
             protocol: Protocol::UniSwapV2,
@@ -89,9 +104,9 @@ pub struct UniswapV2SwapEvent {
 }
 
 impl UniswapV2SwapEvent {
-    pub async fn try_from_log(log: &Log, provider: Arc<Provider<Http>>) -> Result<Self> {
+    pub async fn try_from_log<M: ethers::providers::Middleware>(log: &Log, provider: &M) -> Result<Self> {
         ensure!(
-            !log.topics.is_empty() && 
+            !log.topics.is_empty() &&
             log.topics[0] == UNISWAP_V2_SWAP_TOPIC,
             "Not a UniswapV3 Swap event"
         );
@@ -119,4 +134,49 @@ impl UniswapV2SwapEvent {
     }
 }
 
+/// The registered `DexProtocol` implementor for Uniswap V2 and its forks.
+pub struct UniswapV2Protocol;
+
+#[async_trait::async_trait]
+impl DexProtocol for UniswapV2Protocol {
+    fn swap_topic(&self) -> H256 {
+        UNISWAP_V2_SWAP_TOPIC
+    }
+
+    fn factory_event_topic(&self) -> H256 {
+        UNISWAP_V2_PAIR_CREATED_TOPIC
+    }
 
+    fn factory_address(&self) -> Address {
+        V2FACTORY_ADDRESS.parse().expect("valid V2 factory address")
+    }
+
+    fn event_filter(&self, block: u64) -> Filter {
+        uniswapv2_event_filter(block)
+    }
+
+    async fn decode_pool_created(&self, log: &Log, provider: Arc<Provider<Http>>) -> Result<Pool> {
+        UniswapV2PairCreated::try_from(log)?.to_pool(provider).await
+    }
+
+    async fn decode_swap(&self, log: &Log, provider: Arc<Provider<Http>>) -> Result<SwapEvent> {
+        let swap = UniswapV2SwapEvent::try_from_log(log, &provider).await?;
+
+        // Exactly one of (amount0In, amount1In) is nonzero depending on which
+        // token was sold; pick that side instead of assuming token0->token1.
+        let (coin_in, coin_out, amount_in, amount_out) = if !swap.amount0_in.is_zero() {
+            (swap.token0, swap.token1, swap.amount0_in, swap.amount1_out)
+        } else {
+            (swap.token1, swap.token0, swap.amount1_in, swap.amount0_out)
+        };
+
+        Ok(SwapEvent {
+            protocol: Protocol::UniSwapV2,
+            pool: Some(swap.pool),
+            coin_in: vec![coin_in],
+            coin_out: vec![coin_out],
+            amounts_in: vec![amount_in],
+            amounts_out: vec![amount_out],
+        })
+    }
+}
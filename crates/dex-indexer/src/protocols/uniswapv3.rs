@@ -1,12 +1,25 @@
 use std::{str::FromStr, sync::Arc};
 
 use ethers::{
-    abi::RawLog, contract::{abigen, EthEvent}, core::types::{Address, Log, H256, U256}, providers::{Http, Provider}
+    abi::RawLog, contract::{abigen, EthEvent}, core::types::{Address, Filter, Log, H256, U256}, providers::{Http, Provider}
 };
 use eyre::{ensure, Ok, Result};
 use serde::Deserialize;
 
-use crate::types::{UNISWAP_V2_SWAP_TOPIC, UNISWAP_V2_SWAP_TOPIC};
+use crate::{
+    protocols::{get_coin_decimals, DexProtocol},
+    types::{Pool, PoolExtra, Protocol, SwapEvent, Token},
+};
+
+pub const UNISWAP_V3_SWAP_TOPIC: H256 = H256([
+    0xc4, 0x20, 0x79, 0xf9, 0x4a, 0x63, 0x50, 0xd7, 0xe6, 0x23, 0x5f, 0x29, 0x17, 0x49, 0x24, 0xf9,
+    0x28, 0xcc, 0x2a, 0xc8, 0x18, 0xeb, 0x64, 0xfe, 0xd8, 0x00, 0x4e, 0x11, 0x5f, 0xbc, 0xca, 0x67,
+]);
+
+pub const UNISWAP_V3_POOL_CREATED_TOPIC: H256 = H256([
+    0x78, 0x3c, 0xca, 0x1c, 0x04, 0x12, 0xdd, 0x0d, 0x69, 0x5e, 0x78, 0x45, 0x68, 0xc9, 0x6d, 0xa2,
+    0xe9, 0xc2, 0x2f, 0xf9, 0x89, 0x35, 0x7a, 0x2e, 0x8b, 0x1d, 0x9b, 0x2b, 0x4e, 0x6b, 0x71, 0x18,
+]);
 
 abigen!(
     IUniswapV3Pool,
@@ -24,11 +37,11 @@ abigen!(
     ]"#,
 );
 
-const V2FACTORY_ADDRESS: &str = "0x1F98431c8aD98523631AE4a59f267346ea31F984";
+const V3FACTORY_ADDRESS: &str = "0x1F98431c8aD98523631AE4a59f267346ea31F984";
 
 pub fn uniswapv3_event_filter(block: u64) -> Filter {
     Filter::new()
-        .address(V2FACTORY_ADDRESS.parse::<Address>()?)
+        .address(V3FACTORY_ADDRESS.parse::<Address>().expect("valid V3 factory address"))
         .event("PoolCreated(address,address,uint24,int24,address)")
         .from_block(block)
 }
@@ -44,11 +57,11 @@ pub struct UniswapV3PoolCreated {
 impl TryFrom<&Log> for UniswapV3PoolCreated {
     type Error = eyre::Error;
 
-    fn try_from(value: &Log) -> std::result::Result<Self> {
-        let token0 = Address::from(log.topics[1]);
-        let token1 = Address::from(log.topics[2]);
-        let fee = U256::from_big_endian(&log.topics[3].as_bytes()[29..32]);
-        let pool = Address::from(&log.data[44..64].try_into()?);
+    fn try_from(value: &Log) -> std::result::Result<Self, Self::Error> {
+        let token0 = Address::from(value.topics[1]);
+        let token1 = Address::from(value.topics[2]);
+        let fee = U256::from_big_endian(&value.topics[3].as_bytes()[29..32]).as_u32();
+        let pool = Address::from(value.data[44..64].try_into()?);
 
         Ok(Self { 
             pool,
@@ -69,13 +82,13 @@ impl UniswapV3PoolCreated {
             Token::new(&self.token1, token1_decimals),
         ];
 
-        let extra = PoolExtra::UniSwapV2 { fee: self.fee };
+        let extra = PoolExtra::UniSwapV3 { fee: self.fee as u64 };
 
-        Ok(Pool { 
-            protocol: Protocol::UniSwapV2,
-            pool: self.pair,
+        Ok(Pool {
+            protocol: Protocol::UniSwapV3,
+            pool: self.pool,
             tokens,
-            extra 
+            extra
         })
     }
 }
@@ -88,12 +101,17 @@ pub struct UniswapV3SwapEvent {
     pub amount0: U256,
     pub amount1: U256,
     pub liquidity: u128,
+    /// `true` if the pool's `amount0` delta in the raw event was positive,
+    /// i.e. token0 flowed into the pool and token1 flowed out. `amount0`/
+    /// `amount1` above are unsigned magnitudes, so this is the only place
+    /// swap direction survives past `try_from_log`.
+    pub zero_for_one: bool,
 }
 
 impl UniswapV3SwapEvent {
-    pub async fn try_from_log(log: &Log, provider: Arc<Provider<Http>>) -> Result<Self> {
+    pub async fn try_from_log<M: ethers::providers::Middleware>(log: &Log, provider: &M) -> Result<Self> {
         ensure!(
-            !log.topics.is_empty() && 
+            !log.topics.is_empty() &&
             log.topics[0] == UNISWAP_V3_SWAP_TOPIC,
             "Not a UniswapV3 Swap event"
         );
@@ -109,28 +127,111 @@ impl UniswapV3SwapEvent {
         let token0_address: Address = pool_contract.token_0().call().await?;
         let token1_address: Address = pool_contract.token_1().call().await?;
 
-        let (token_0, token_1, amount_0, amount_1, liquility);
-
-        if parsed_log.amount_0 > 0.into() {
-            token_0 = token0_address;
-            amount_0 = parsed_log.amount_0.into_raw();
-            token_1 = token1_address;
-            amount_1 = (-parsed_log.amount_1).into_raw();
+        let zero_for_one = parsed_log.amount_0 > 0.into();
+        let (amount_0, amount_1) = if zero_for_one {
+            (parsed_log.amount_0.into_raw(), (-parsed_log.amount_1).into_raw())
         } else {
-            token_1 = token1_address;
-            amount_1 = parsed_log.amount_1.into_raw();
-            token_0 = token0_address;
-            amount_0 = (-parsed_log.amount_0).into_raw();
-        }
-        liquility = parsed_log.liquidity;
+            ((-parsed_log.amount_0).into_raw(), parsed_log.amount_1.into_raw())
+        };
 
         Ok(Self {
             pool: pool_address,
-            token0: token_0,
-            token1: token_1,
+            token0: token0_address,
+            token1: token1_address,
             amount0: amount_0,
             amount1: amount_1,
-            liquidity: liquility,
+            liquidity: parsed_log.liquidity,
+            zero_for_one,
         })
     }
 }
+
+/// Resolves `(coin_in, coin_out, amount_in, amount_out)` from a decoded swap.
+/// `amount0`/`amount1` are unsigned magnitudes, so `zero_for_one` is what
+/// tells us which side was actually sold.
+fn swap_direction(swap: &UniswapV3SwapEvent) -> (Address, Address, U256, U256) {
+    if swap.zero_for_one {
+        (swap.token0, swap.token1, swap.amount0, swap.amount1)
+    } else {
+        (swap.token1, swap.token0, swap.amount1, swap.amount0)
+    }
+}
+
+/// The registered `DexProtocol` implementor for Uniswap V3.
+pub struct UniswapV3Protocol;
+
+#[async_trait::async_trait]
+impl DexProtocol for UniswapV3Protocol {
+    fn swap_topic(&self) -> H256 {
+        UNISWAP_V3_SWAP_TOPIC
+    }
+
+    fn factory_event_topic(&self) -> H256 {
+        UNISWAP_V3_POOL_CREATED_TOPIC
+    }
+
+    fn factory_address(&self) -> Address {
+        V3FACTORY_ADDRESS.parse().expect("valid V3 factory address")
+    }
+
+    fn event_filter(&self, block: u64) -> Filter {
+        uniswapv3_event_filter(block)
+    }
+
+    async fn decode_pool_created(&self, log: &Log, provider: Arc<Provider<Http>>) -> Result<Pool> {
+        UniswapV3PoolCreated::try_from(log)?.to_pool(provider).await
+    }
+
+    async fn decode_swap(&self, log: &Log, provider: Arc<Provider<Http>>) -> Result<SwapEvent> {
+        let swap = UniswapV3SwapEvent::try_from_log(log, &provider).await?;
+        let (coin_in, coin_out, amount_in, amount_out) = swap_direction(&swap);
+
+        Ok(SwapEvent {
+            protocol: Protocol::UniSwapV3,
+            pool: Some(swap.pool),
+            coin_in: vec![coin_in],
+            coin_out: vec![coin_out],
+            amounts_in: vec![amount_in],
+            amounts_out: vec![amount_out],
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn swap_event(zero_for_one: bool) -> UniswapV3SwapEvent {
+        UniswapV3SwapEvent {
+            pool: Address::from_low_u64_be(1),
+            token0: Address::from_low_u64_be(2),
+            token1: Address::from_low_u64_be(3),
+            amount0: U256::from(100),
+            amount1: U256::from(200),
+            liquidity: 0,
+            zero_for_one,
+        }
+    }
+
+    #[test]
+    fn decode_direction_follows_zero_for_one() {
+        let swap = swap_event(true);
+        let (coin_in, coin_out, amount_in, amount_out) = swap_direction(&swap);
+
+        assert_eq!(coin_in, swap.token0);
+        assert_eq!(coin_out, swap.token1);
+        assert_eq!(amount_in, swap.amount0);
+        assert_eq!(amount_out, swap.amount1);
+    }
+
+    #[test]
+    fn decode_direction_flips_when_token1_is_sold() {
+        let swap = swap_event(false);
+        let (coin_in, coin_out, amount_in, amount_out) = swap_direction(&swap);
+
+        assert_eq!(coin_in, swap.token1);
+        assert_eq!(coin_out, swap.token0);
+        assert_eq!(amount_in, swap.amount1);
+        assert_eq!(amount_out, swap.amount0);
+    }
+}
@@ -0,0 +1,316 @@
+use std::{
+    collections::HashMap,
+    fs::{File, OpenOptions},
+    io::{BufRead, BufReader, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use ethers::{
+    core::types::{Address, BlockNumber, H256},
+    providers::Middleware,
+};
+use eyre::{ensure, eyre, Result};
+
+use crate::{indexer::PoolIndexer, types::Pool};
+
+/// Sized to the low end of common RPC provider limits (Infura/Alchemy cap around
+/// 2000-10000 blocks or a result-size limit, whichever hits first).
+const BACKFILL_CHUNK_SIZE: u64 = 2_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Cursor {
+    pub block_number: u64,
+    pub block_hash: H256,
+}
+
+pub trait PoolStoreBackend {
+    fn load_all(&self) -> Result<(HashMap<Address, Pool>, Option<Cursor>)>;
+    fn insert(&mut self, pool: &Pool) -> Result<()>;
+    fn remove(&mut self, pool: &Address) -> Result<()>;
+    fn cursor(&self) -> Result<Option<Cursor>>;
+    fn set_cursor(&mut self, cursor: Cursor) -> Result<()>;
+}
+
+/// Appends pools in the existing `protocol|pool|tokens|extra` line format to
+/// a flat file, with the cursor tracked in a sibling file: the same path with
+/// its extension replaced by `cursor` (`pools.txt` -> `pools.cursor`).
+pub struct FileBackend {
+    pools_path: PathBuf,
+    cursor_path: PathBuf,
+}
+
+impl FileBackend {
+    pub fn new(pools_path: impl Into<PathBuf>) -> Self {
+        let pools_path = pools_path.into();
+        let cursor_path = pools_path.with_extension("cursor");
+        Self { pools_path, cursor_path }
+    }
+
+    fn append_line(&self, line: &str) -> Result<()> {
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.pools_path)?;
+        writeln!(file, "{line}")?;
+        Ok(())
+    }
+
+    fn rewrite(&self, pools: &HashMap<Address, Pool>) -> Result<()> {
+        let mut file = File::create(&self.pools_path)?;
+        for pool in pools.values() {
+            writeln!(file, "{pool}")?;
+        }
+        Ok(())
+    }
+}
+
+impl PoolStoreBackend for FileBackend {
+    fn load_all(&self) -> Result<(HashMap<Address, Pool>, Option<Cursor>)> {
+        let mut pools = HashMap::new();
+
+        if self.pools_path.exists() {
+            let reader = BufReader::new(File::open(&self.pools_path)?);
+            for line in reader.lines() {
+                let line = line?;
+                if line.trim().is_empty() {
+                    continue;
+                }
+                let pool = Pool::try_from(line.as_str())?;
+                pools.insert(pool.pool, pool);
+            }
+        }
+
+        let cursor = if self.cursor_path.exists() {
+            let raw = std::fs::read_to_string(&self.cursor_path)?;
+            Some(parse_cursor(raw.trim())?)
+        } else {
+            None
+        };
+
+        Ok((pools, cursor))
+    }
+
+    fn insert(&mut self, pool: &Pool) -> Result<()> {
+        self.append_line(&pool.to_string())
+    }
+
+    fn remove(&mut self, pool: &Address) -> Result<()> {
+        let (mut pools, _) = self.load_all()?;
+        pools.remove(pool);
+        self.rewrite(&pools)
+    }
+
+    fn cursor(&self) -> Result<Option<Cursor>> {
+        if !self.cursor_path.exists() {
+            return Ok(None);
+        }
+        let raw = std::fs::read_to_string(&self.cursor_path)?;
+        Ok(Some(parse_cursor(raw.trim())?))
+    }
+
+    fn set_cursor(&mut self, cursor: Cursor) -> Result<()> {
+        std::fs::write(&self.cursor_path, format!("{}|{:#x}", cursor.block_number, cursor.block_hash))?;
+        Ok(())
+    }
+}
+
+/// The block to resume backfill from: one past the last persisted cursor,
+/// or `from_block` if there's no cursor yet or it's behind the requested range.
+fn resume_block(cursor: Option<Cursor>, from_block: u64) -> u64 {
+    cursor.map(|c| c.block_number + 1).unwrap_or(from_block).max(from_block)
+}
+
+fn next_chunk_end(chunk_start: u64, to_block: u64) -> u64 {
+    (chunk_start + BACKFILL_CHUNK_SIZE - 1).min(to_block)
+}
+
+fn parse_cursor(raw: &str) -> Result<Cursor> {
+    let parts: Vec<&str> = raw.split('|').collect();
+    ensure!(parts.len() == 2, "invalid cursor format: {}", raw);
+    Ok(Cursor {
+        block_number: parts[0].parse()?,
+        block_hash: parts[1].parse()?,
+    })
+}
+
+pub struct PoolStore<B: PoolStoreBackend> {
+    backend: B,
+}
+
+impl<B: PoolStoreBackend> PoolStore<B> {
+    pub fn new(backend: B) -> Self {
+        Self { backend }
+    }
+
+    pub fn load(&self) -> Result<(HashMap<Address, Pool>, Option<Cursor>)> {
+        self.backend.load_all()
+    }
+
+    pub fn insert(&mut self, pool: &Pool) -> Result<()> {
+        self.backend.insert(pool)
+    }
+
+    pub fn remove(&mut self, pool: &Address) -> Result<()> {
+        self.backend.remove(pool)
+    }
+
+    pub fn advance_cursor(&mut self, cursor: Cursor) -> Result<()> {
+        self.backend.set_cursor(cursor)
+    }
+}
+
+impl PoolStore<FileBackend> {
+    pub fn open(path: impl AsRef<Path>) -> Self {
+        Self::new(FileBackend::new(path.as_ref()))
+    }
+}
+
+/// Pages historical `PoolCreated`/`PairCreated` logs from `from_block` up to
+/// `to_block` in `BACKFILL_CHUNK_SIZE`-block windows, persisting the cursor
+/// after each chunk so an interrupted backfill resumes where it stopped.
+pub async fn backfill<B: PoolStoreBackend>(
+    indexer: &mut PoolIndexer,
+    store: &mut PoolStore<B>,
+    provider: Arc<ethers::providers::Provider<ethers::providers::Http>>,
+    from_block: u64,
+    to_block: u64,
+) -> Result<()> {
+    ensure!(from_block <= to_block, "from_block must not exceed to_block");
+
+    let (mut known_pools, cursor) = store.load()?;
+    let resume_from = resume_block(cursor, from_block);
+    indexer.seed(
+        known_pools.clone(),
+        cursor.map(|c| (c.block_number, c.block_hash)).unwrap_or((from_block.saturating_sub(1), H256::zero())),
+    );
+
+    let registry = crate::protocols::default_registry();
+    let mut chunk_start = resume_from;
+
+    while chunk_start <= to_block {
+        let chunk_end = next_chunk_end(chunk_start, to_block);
+
+        for protocol in registry.protocols() {
+            let filter = protocol
+                .event_filter(chunk_start)
+                .to_block(BlockNumber::Number(chunk_end.into()));
+
+            let logs = provider.as_ref().get_logs(&filter).await.map_err(|e| eyre!(e))?;
+            for log in logs {
+                let pool = protocol.decode_pool_created(&log, provider.clone()).await?;
+                // A crash-and-retry re-fetches the same chunk; skip pools the
+                // store already has instead of appending a duplicate line.
+                if known_pools.insert(pool.pool, pool.clone()).is_none() {
+                    store.insert(&pool)?;
+                }
+            }
+        }
+
+        let block = provider
+            .get_block(BlockNumber::Number(chunk_end.into()))
+            .await
+            .map_err(|e| eyre!(e))?
+            .ok_or_else(|| eyre!("block {} not found during backfill", chunk_end))?;
+        let hash = block.hash.ok_or_else(|| eyre!("block {} has no hash", chunk_end))?;
+
+        store.advance_cursor(Cursor { block_number: chunk_end, block_hash: hash })?;
+
+        chunk_start = chunk_end + 1;
+    }
+
+    // Reload from the store so `indexer.pools()` reflects what backfill just persisted.
+    let (pools, cursor) = store.load()?;
+    indexer.seed(pools, cursor.map(|c| (c.block_number, c.block_hash)).unwrap_or((to_block, H256::zero())));
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{Protocol, PoolExtra};
+
+    fn temp_path(name: &str) -> PathBuf {
+        let mut path = std::env::temp_dir();
+        path.push(format!("eth-mev-store-test-{}-{name}", std::process::id()));
+        path
+    }
+
+    fn sample_pool(seed: u8) -> Pool {
+        Pool {
+            protocol: Protocol::UniSwapV2,
+            pool: Address::from_low_u64_be(seed as u64),
+            tokens: Vec::new(),
+            extra: PoolExtra::None,
+        }
+    }
+
+    #[test]
+    fn file_backend_round_trips_pools_and_cursor() {
+        let path = temp_path("round_trip");
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("cursor"));
+
+        let mut backend = FileBackend::new(&path);
+        let pool = sample_pool(1);
+        backend.insert(&pool).unwrap();
+        let cursor = Cursor { block_number: 42, block_hash: H256::repeat_byte(7) };
+        backend.set_cursor(cursor).unwrap();
+
+        let (loaded_pools, loaded_cursor) = backend.load_all().unwrap();
+
+        assert_eq!(loaded_pools.len(), 1);
+        assert!(loaded_pools.contains_key(&pool.pool));
+        assert_eq!(loaded_cursor, Some(cursor));
+
+        let _ = std::fs::remove_file(&path);
+        let _ = std::fs::remove_file(path.with_extension("cursor"));
+    }
+
+    #[test]
+    fn file_backend_remove_rewrites_the_file_without_that_pool() {
+        let path = temp_path("remove");
+        let _ = std::fs::remove_file(&path);
+
+        let mut backend = FileBackend::new(&path);
+        let pool_a = sample_pool(1);
+        let pool_b = sample_pool(2);
+        backend.insert(&pool_a).unwrap();
+        backend.insert(&pool_b).unwrap();
+
+        backend.remove(&pool_a.pool).unwrap();
+
+        let (loaded_pools, _) = backend.load_all().unwrap();
+        assert_eq!(loaded_pools.len(), 1);
+        assert!(loaded_pools.contains_key(&pool_b.pool));
+
+        let _ = std::fs::remove_file(&path);
+    }
+
+    #[test]
+    fn resume_block_continues_after_the_last_persisted_cursor() {
+        let cursor = Some(Cursor { block_number: 150, block_hash: H256::zero() });
+        assert_eq!(resume_block(cursor, 100), 151);
+    }
+
+    #[test]
+    fn resume_block_falls_back_to_from_block_without_a_cursor() {
+        assert_eq!(resume_block(None, 100), 100);
+    }
+
+    #[test]
+    fn resume_block_never_goes_below_from_block_for_a_stale_cursor() {
+        let cursor = Some(Cursor { block_number: 10, block_hash: H256::zero() });
+        assert_eq!(resume_block(cursor, 100), 100);
+    }
+
+    #[test]
+    fn chunk_end_caps_at_to_block_for_the_final_partial_chunk() {
+        let to_block = 100 + BACKFILL_CHUNK_SIZE / 2;
+        assert_eq!(next_chunk_end(100, to_block), to_block);
+    }
+
+    #[test]
+    fn chunk_end_spans_a_full_chunk_when_more_range_remains() {
+        let to_block = 100 + BACKFILL_CHUNK_SIZE * 10;
+        assert_eq!(next_chunk_end(100, to_block), 100 + BACKFILL_CHUNK_SIZE - 1);
+    }
+}
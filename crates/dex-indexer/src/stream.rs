@@ -0,0 +1,360 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::Arc,
+    time::Duration,
+};
+
+use ethers::{
+    core::types::{Address, Filter, Log, H256},
+    providers::{Http, Ipc, Middleware, Provider, PubsubClient, Ws},
+};
+use eyre::{eyre, Result};
+use futures::StreamExt;
+use tokio::sync::mpsc;
+
+use crate::{
+    protocols::{default_registry, ProtocolRegistry},
+    types::SwapEvent,
+};
+
+const INITIAL_BACKOFF: Duration = Duration::from_millis(500);
+const MAX_BACKOFF: Duration = Duration::from_secs(30);
+
+fn next_backoff(current: Duration) -> Duration {
+    (current * 2).min(MAX_BACKOFF)
+}
+
+/// Identifies a log for de-dup across the overlapping replay/subscription window.
+fn log_id(log: &Log) -> Option<(H256, u64)> {
+    Some((log.transaction_hash?, log.log_index?.as_u64()))
+}
+
+/// How far behind the current high-water mark a de-duped log id is kept.
+/// The overlap `seen` actually needs to cover is one replay/subscribe
+/// reconnect cycle, not the process's entire uptime.
+const DEDUP_WINDOW_BLOCKS: u64 = 256;
+
+/// Bounded de-dup set for the replay/subscribe overlap window: tracks the
+/// block each id was seen at so entries can be evicted once they fall more
+/// than `DEDUP_WINDOW_BLOCKS` behind the stream's high-water mark, instead of
+/// growing for the lifetime of the connection.
+#[derive(Default)]
+struct SeenLogs {
+    seen: HashMap<(H256, u64), u64>,
+}
+
+impl SeenLogs {
+    /// Records `id` as seen at `block_number`. Returns `false` if it was
+    /// already present, i.e. this log is a duplicate and should be skipped.
+    fn insert(&mut self, id: (H256, u64), block_number: u64) -> bool {
+        self.seen.insert(id, block_number).is_none()
+    }
+
+    /// Drops every id more than `DEDUP_WINDOW_BLOCKS` behind `high_water`.
+    fn prune(&mut self, high_water: u64) {
+        let floor = high_water.saturating_sub(DEDUP_WINDOW_BLOCKS);
+        self.seen.retain(|_, &mut block_number| block_number >= floor);
+    }
+}
+
+/// The slice of `Middleware` that `replay_missed` needs, factored out so its
+/// gap-filling/de-dup logic can be exercised against a fake source in tests
+/// instead of a live provider.
+#[async_trait::async_trait]
+trait LogSource {
+    async fn block_number(&self) -> Result<u64>;
+    async fn logs(&self, filter: &Filter) -> Result<Vec<Log>>;
+}
+
+#[async_trait::async_trait]
+impl<M: Middleware> LogSource for M {
+    async fn block_number(&self) -> Result<u64> {
+        Ok(self.get_block_number().await.map_err(|e| eyre!(e))?.as_u64())
+    }
+
+    async fn logs(&self, filter: &Filter) -> Result<Vec<Log>> {
+        self.get_logs(filter).await.map_err(|e| eyre!(e))
+    }
+}
+
+/// Fetches logs from `last_seen_block + 1` up to the source's current block,
+/// de-duping against `seen` so logs already delivered by an overlapping live
+/// subscription aren't replayed twice. Returns the new high-water mark and
+/// the logs to actually deliver.
+async fn collect_replay_logs<S: LogSource>(
+    source: &S,
+    filter: Filter,
+    last_seen_block: u64,
+    seen: &mut SeenLogs,
+) -> Result<(u64, Vec<Log>)> {
+    let current = source.block_number().await?;
+    if current <= last_seen_block {
+        return Ok((last_seen_block, Vec::new()));
+    }
+
+    let filter = filter.from_block(last_seen_block + 1).to_block(current);
+    let logs = source.logs(&filter).await?;
+
+    let mut new_last_seen = last_seen_block;
+    let mut fresh = Vec::new();
+    for log in logs {
+        let block_number = log.block_number.map(|n| n.as_u64());
+        if let (Some(id), Some(block_number)) = (log_id(&log), block_number) {
+            if !seen.insert(id, block_number) {
+                continue;
+            }
+        }
+        if let Some(block_number) = block_number {
+            new_last_seen = new_last_seen.max(block_number);
+        }
+        fresh.push(log);
+    }
+
+    seen.prune(new_last_seen);
+    Ok((new_last_seen, fresh))
+}
+
+#[async_trait::async_trait]
+trait ReconnectableProvider: Sized {
+    async fn reconnect(endpoint: &str) -> Result<Self>;
+}
+
+#[async_trait::async_trait]
+impl ReconnectableProvider for Provider<Ws> {
+    async fn reconnect(endpoint: &str) -> Result<Self> {
+        Ok(Provider::<Ws>::connect(endpoint).await?)
+    }
+}
+
+#[async_trait::async_trait]
+impl ReconnectableProvider for Provider<Ipc> {
+    async fn reconnect(endpoint: &str) -> Result<Self> {
+        Ok(Provider::<Ipc>::connect(endpoint).await?)
+    }
+}
+
+pub struct SwapStream<M: Middleware + PubsubClient + ReconnectableProvider + 'static> {
+    provider: Arc<M>,
+    /// The WS URL / IPC path `provider` was built from, so a dead connection
+    /// can be re-dialed from scratch.
+    endpoint: String,
+    http_provider: Arc<Provider<Http>>,
+    registry: ProtocolRegistry,
+    pools: HashSet<Address>,
+    last_seen_block: u64,
+}
+
+impl SwapStream<Provider<Ws>> {
+    pub async fn connect_ws(url: &str, http_url: &str, pools: HashSet<Address>, start_block: u64) -> Result<Self> {
+        let provider = Provider::<Ws>::connect(url).await?;
+        let http_provider = Arc::new(Provider::<Http>::try_from(http_url)?);
+        Ok(Self {
+            provider: Arc::new(provider),
+            endpoint: url.to_string(),
+            http_provider,
+            registry: default_registry(),
+            pools,
+            last_seen_block: start_block,
+        })
+    }
+}
+
+impl SwapStream<Provider<Ipc>> {
+    pub async fn connect_ipc(path: &str, http_url: &str, pools: HashSet<Address>, start_block: u64) -> Result<Self> {
+        let provider = Provider::<Ipc>::connect(path).await?;
+        let http_provider = Arc::new(Provider::<Http>::try_from(http_url)?);
+        Ok(Self {
+            provider: Arc::new(provider),
+            endpoint: path.to_string(),
+            http_provider,
+            registry: default_registry(),
+            pools,
+            last_seen_block: start_block,
+        })
+    }
+}
+
+impl<M: Middleware + PubsubClient + ReconnectableProvider + 'static> SwapStream<M> {
+    fn filter(&self) -> Filter {
+        Filter::new()
+            .address(self.pools.iter().copied().collect::<Vec<_>>())
+            .topic0(self.registry.swap_topics().copied().collect::<Vec<_>>())
+    }
+
+    async fn decode(&self, log: Log) -> Result<SwapEvent> {
+        let topic = *log.topics.first().ok_or_else(|| eyre!("swap log has no topics"))?;
+        let protocol = self
+            .registry
+            .by_swap_topic(&topic)
+            .ok_or_else(|| eyre!("no registered protocol for swap topic {:#x}", topic))?;
+        protocol.decode_swap(&log, self.http_provider.clone()).await
+    }
+
+    async fn replay_missed(&mut self, tx: &mpsc::Sender<SwapEvent>, seen: &mut SeenLogs) -> Result<()> {
+        let filter = self.filter();
+        let (last_seen_block, logs) =
+            collect_replay_logs(self.provider.as_ref(), filter, self.last_seen_block, seen).await?;
+        self.last_seen_block = last_seen_block;
+
+        for log in logs {
+            if let Ok(event) = self.decode(log).await {
+                let _ = tx.send(event).await;
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn reconnect(&mut self, backoff: &mut Duration) {
+        loop {
+            tokio::time::sleep(*backoff).await;
+            *backoff = next_backoff(*backoff);
+
+            match M::reconnect(&self.endpoint).await {
+                Ok(provider) => {
+                    self.provider = Arc::new(provider);
+                    return;
+                }
+                Err(_) => continue,
+            }
+        }
+    }
+
+    pub async fn run(mut self, tx: mpsc::Sender<SwapEvent>) -> Result<()> {
+        let mut backoff = INITIAL_BACKOFF;
+
+        loop {
+            let filter = self.filter();
+            let mut subscription = match self.provider.subscribe_logs(&filter).await {
+                Ok(sub) => sub,
+                Err(_) => {
+                    self.reconnect(&mut backoff).await;
+                    continue;
+                }
+            };
+
+            // Subscribe before replaying: anything emitted between the
+            // subscribe call and replay's snapshot would otherwise land in
+            // neither. Replay covers the gap up to whatever block was
+            // already live, de-duping against what the subscription
+            // redelivers for that overlap.
+            let mut seen = SeenLogs::default();
+            if self.replay_missed(&tx, &mut seen).await.is_err() {
+                self.reconnect(&mut backoff).await;
+                continue;
+            }
+
+            backoff = INITIAL_BACKOFF;
+
+            while let Some(log) = subscription.next().await {
+                let block_number = log.block_number.map(|n| n.as_u64());
+                if let (Some(id), Some(block_number)) = (log_id(&log), block_number) {
+                    if !seen.insert(id, block_number) {
+                        continue;
+                    }
+                }
+                if let Some(block_number) = block_number {
+                    self.last_seen_block = self.last_seen_block.max(block_number);
+                    seen.prune(self.last_seen_block);
+                }
+                if let Ok(event) = self.decode(log).await {
+                    if tx.send(event).await.is_err() {
+                        // receiver gone, nothing left to do
+                        return Ok(());
+                    }
+                }
+            }
+
+            // Socket dropped; `replay_missed` picks up anything emitted while we were down.
+            self.reconnect(&mut backoff).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct FakeLogSource {
+        block_number: u64,
+        logs: Vec<Log>,
+    }
+
+    #[async_trait::async_trait]
+    impl LogSource for FakeLogSource {
+        async fn block_number(&self) -> Result<u64> {
+            Ok(self.block_number)
+        }
+
+        async fn logs(&self, _filter: &Filter) -> Result<Vec<Log>> {
+            Ok(self.logs.clone())
+        }
+    }
+
+    fn log_at(block_number: u64, tx_index: u64) -> Log {
+        Log {
+            block_number: Some(block_number.into()),
+            transaction_hash: Some(H256::from_low_u64_be(tx_index)),
+            log_index: Some(tx_index.into()),
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn replay_fetches_everything_after_last_seen_block() {
+        let source = FakeLogSource { block_number: 110, logs: vec![log_at(105, 1), log_at(108, 2)] };
+        let mut seen = SeenLogs::default();
+
+        let (last_seen, logs) = collect_replay_logs(&source, Filter::new(), 100, &mut seen).await.unwrap();
+
+        assert_eq!(last_seen, 108);
+        assert_eq!(logs.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn replay_is_a_noop_once_caught_up() {
+        let source = FakeLogSource { block_number: 100, logs: vec![log_at(100, 1)] };
+        let mut seen = SeenLogs::default();
+
+        let (last_seen, logs) = collect_replay_logs(&source, Filter::new(), 100, &mut seen).await.unwrap();
+
+        assert_eq!(last_seen, 100);
+        assert!(logs.is_empty());
+    }
+
+    #[tokio::test]
+    async fn replay_does_not_redeliver_logs_already_seen_from_the_live_subscription() {
+        let overlap = log_at(105, 1);
+        let source = FakeLogSource { block_number: 110, logs: vec![overlap.clone(), log_at(108, 2)] };
+        let mut seen = SeenLogs::default();
+        seen.insert(log_id(&overlap).unwrap(), 105);
+
+        let (_, logs) = collect_replay_logs(&source, Filter::new(), 100, &mut seen).await.unwrap();
+
+        assert_eq!(logs.len(), 1);
+        assert_eq!(logs[0].block_number, Some(108.into()));
+    }
+
+    #[test]
+    fn backoff_doubles_and_caps_at_max() {
+        let mut backoff = INITIAL_BACKOFF;
+        for _ in 0..10 {
+            backoff = next_backoff(backoff);
+        }
+        assert_eq!(backoff, MAX_BACKOFF);
+    }
+
+    #[test]
+    fn seen_logs_prunes_entries_outside_the_dedup_window() {
+        let mut seen = SeenLogs::default();
+        let old_id = (H256::from_low_u64_be(1), 0);
+        let recent_id = (H256::from_low_u64_be(2), 0);
+
+        seen.insert(old_id, 100);
+        seen.insert(recent_id, 900);
+        seen.prune(900 + DEDUP_WINDOW_BLOCKS);
+
+        assert!(seen.insert(old_id, 900 + DEDUP_WINDOW_BLOCKS), "entry far behind the high-water mark must be evicted");
+        assert!(!seen.insert(recent_id, 900 + DEDUP_WINDOW_BLOCKS), "entry still inside the window must be retained");
+    }
+}
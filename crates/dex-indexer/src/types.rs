@@ -1,24 +1,8 @@
-use std::{
-    collections::HashSet, fmt, hash::{Hash, Hasher}, ops::Add, sync::Arc
-};
-use burberry::{async_trait, Executor};
-use ethers::{providers::Provider, core::types::{Address, Log, H256, Filter}};
-use eyre::{bail, ensure, Ok};
-use serde::{Deserialize, Serialize};
-
-use crate::protocols::uniswapv3::uniswapv3_event_filter;
-
-pub const UNISWAP_V2_SWAP_TOPIC: H256 = H256([
-    0xd7, 0x8a, 0xd9, 0x5f, 0xa4, 0x6c, 0x99, 0x4b, 0x65, 0x51, 0xd0, 0xda, 0x85, 0xfc, 0x27, 0x5f, 
-    0xe6, 0x13, 0xce, 0x37, 0x65, 0x7f, 0xb8, 0xd5, 0xe3, 0xd1, 0x30, 0x84, 0x01, 0x59, 0xd8, 0x22,
-]);
-
-pub const UNISWAP_V3_SWAP_TOPIC: H256 = H256([
-    0xc4, 0x20, 0x79, 0xf9, 0x4a, 0x63, 0x50, 0xd7, 0xe6, 0x23, 0x5f, 0x29, 0x17, 0x49, 0x24, 0xf9,
-    0x28, 0xcc, 0x2a, 0xc8, 0x18, 0xeb, 0x64, 0xfe, 0xd8, 0x00, 0x4e, 0x11, 0x5f, 0xbc, 0xca, 0x67,
-]);
-
+use std::fmt;
 
+use ethers::core::types::{Address, U256};
+use eyre::{bail, ensure, Result};
+use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
 pub struct Pool {
@@ -48,7 +32,14 @@ pub enum PoolExtra {
     },
     UniSwapV3 {
         fee: u64,
-    }
+    },
+    Curve {
+        fee: u64,
+    },
+    Balancer {
+        pool_id: [u8; 32],
+        fee: u64,
+    },
 }
 
 impl fmt::Display for Pool {
@@ -81,7 +72,7 @@ impl TryFrom<&str> for Pool {
             protocol,
             pool,
             tokens,
-            extra, 
+            extra,
         })
     }
 }
@@ -104,7 +95,7 @@ impl Pool {
     }
 
     pub fn token(&self, index: usize) -> Option<Token> {
-        self.tokens.get(index)
+        self.tokens.get(index).cloned()
     }
 
     // (token0_address, token1_address)
@@ -135,8 +126,8 @@ pub struct SwapEvent {
     pub pool: Option<Address>,
     pub coin_in: Vec<Address>,
     pub coin_out: Vec<Address>,
-    pub amounts_in: Vec<u64>,
-    pub amounts_out: Vec<u64>,
+    pub amounts_in: Vec<U256>,
+    pub amounts_out: Vec<U256>,
 }
 
 impl SwapEvent {
@@ -149,6 +140,8 @@ impl SwapEvent {
 pub enum Protocol {
     UniSwapV2,
     UniSwapV3,
+    Curve,
+    Balancer,
 }
 
 impl fmt::Display for Protocol {
@@ -156,6 +149,8 @@ impl fmt::Display for Protocol {
         match self {
             Protocol::UniSwapV2 => write!(f, "uniswapv2"),
             Protocol::UniSwapV3 => write!(f, "uniswapv3"),
+            Protocol::Curve => write!(f, "curve"),
+            Protocol::Balancer => write!(f, "balancer"),
         }
     }
 }
@@ -167,45 +162,9 @@ impl TryFrom<&str> for Protocol {
         match value {
             "uniswapv2" => Ok(Protocol::UniSwapV2),
             "uniswapv3" => Ok(Protocol::UniSwapV3),
+            "curve" => Ok(Protocol::Curve),
+            "balancer" => Ok(Protocol::Balancer),
             _ => bail!("Unsupported protocol: {}", value),
         }
     }
 }
-
-impl TryFrom<&Log> for Protocol {
-    type Error = eyre::Error;
-
-    fn try_from(value: &Log) -> Result<Self> {
-        
-    }
-}
-
-impl Protocol {
-    pub fn try_from_event_topic(topic: &H256) -> Result<Self> {
-        match topic {
-            UNISWAP_V2_SWAP_TOPIC => {
-                Ok(Protocol::UniSwapV2)
-            }
-            UNISWAP_V2_SWAP_TOPIC => {
-                Ok(Protocol::UniSwapV3)
-            }
-            _ => bail!("Not interesting")
-        }
-    }
-
-    pub fn event_filter(&self, block: u64) -> Filter {
-        match self {
-            Protocol::UniSwapV2 => {
-                uniswapv2_event_filter(block)
-            }
-            Protocol::UniSwapV3 => {
-                uniswapv3_event_filter(block)
-            }
-            _ => todo!(),
-        }
-    }
-
-    pub async fn eth_event_to_pool(&self, log: &Log, provider: &Provider<Provider>) -> Result<Pool> {
-        
-    }
-}